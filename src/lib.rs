@@ -1,11 +1,266 @@
 //! When working with iterators/futures or other hign-generic types sometimes it's useful to assert
 //! that type of some expression implements some traits or even cast smt to `impl Trait`.
-//! 
+//!
 //! This crate provides macros for both goals — [`assert_bound`] and [`as_opaque`].
 //!
 //! [`assert_bound`]: ./macro.assert_bound.html
 //! [`as_opaque`]: ./macro.as_opaque.html
 
+/// Parses a `+`-separated list of trait bounds (each optionally generic, e.g.
+/// `Iterator<Item = u32>`) and hands the result to a callback macro.
+///
+/// This is a tt-muncher: it walks the input bound-by-bound so that bounds
+/// with generic arguments (which can't simply be captured with a single
+/// `$bound:ty` or `$bound:path` fragment without becoming ambiguous with the
+/// rest of the input) can be parsed properly. The callback is invoked as
+/// `$callback!($($cbargs)* wheres = [...], impls = [...], bounds = [...],
+/// lifetimes = [...])` where `wheres` is one `__EXPR_TYPE: Bound,` predicate
+/// per parsed bound, `impls` is the same bounds joined with `+` (for use in
+/// `impl Trait` position), `bounds` is the individual bounds each wrapped in
+/// its own `[...]` group (used by [`assert_not_bound`], which needs to probe
+/// each bound separately rather than jointly), and `lifetimes` holds whatever
+/// followed a trailing `;` (used by [`as_opaque`]'s lifetime suffix), if any.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_bound_munch {
+    (@cont wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*],
+     cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*], rest = []) => {
+        $($cb)+!{$($cbargs)* wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], lifetimes = []}
+    };
+    (@cont wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*],
+     cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*], rest = [; $($lifetimes:tt)*]) => {
+        $($cb)+!{$($cbargs)* wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], lifetimes = [$($lifetimes)*]}
+    };
+    (@cont wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*],
+     cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*], rest = [+ $($rest:tt)*]) => {
+        $crate::__assert_bound_munch!{@bound
+            wheres = [$($wheres)*], impls = [$($impls)* +], bounds = [$($bounds)*],
+            cb = [$($cb)+], cbargs = [$($cbargs)*], rest = [$($rest)*]}
+    };
+
+    // A standalone lifetime bound, e.g. `'static`.
+    (@bound wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*],
+     cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     rest = [$lifetime:lifetime $($rest:tt)*]) => {
+        $crate::__assert_bound_munch!{@cont
+            wheres = [$($wheres)* __EXPR_TYPE: $lifetime,],
+            impls = [$($impls)* $lifetime],
+            bounds = [$($bounds)* [$lifetime]],
+            cb = [$($cb)+], cbargs = [$($cbargs)*], rest = [$($rest)*]}
+    };
+
+    // A relaxed/marker bound, e.g. `?Sized`.
+    (@bound wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*],
+     cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     rest = [? $head:ident $($rest:tt)*]) => {
+        $crate::__assert_bound_marker!{
+            wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], cb = [$($cb)+], cbargs = [$($cbargs)*],
+            path = [$head], rest = [$($rest)*]
+        }
+    };
+
+    // A higher-ranked bound, e.g. `for<'a> Fn(&'a str) -> &'a str`. The
+    // lifetime list is parsed in one go (unlike e.g. `__assert_bound_path`'s
+    // `::segment`s) since it's delimited by `>`, so there's no ambiguity with
+    // an unbounded trailing `tt` repetition.
+    (@bound wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*],
+     cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     rest = [for < $($lt:lifetime),+ $(,)? > $head:ident $($rest:tt)*]) => {
+        $crate::__assert_bound_path!{
+            wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], cb = [$($cb)+], cbargs = [$($cbargs)*],
+            hrtb = [for < $($lt),+ >], path = [$head], rest = [$($rest)*]
+        }
+    };
+
+    // Parse one bound's leading `head::tail` path.
+    (@bound wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*],
+     cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     rest = [$head:ident $($rest:tt)*]) => {
+        $crate::__assert_bound_path!{
+            wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], cb = [$($cb)+], cbargs = [$($cbargs)*],
+            hrtb = [], path = [$head], rest = [$($rest)*]
+        }
+    };
+}
+
+/// Consumes `:: segment` pieces of a `?`-relaxed marker bound (e.g. `?Sized`),
+/// one at a time, for the same reason as [`__assert_bound_path`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_bound_marker {
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     path = [$($path:tt)*], rest = [:: $seg:ident $($rest:tt)*]) => {
+        $crate::__assert_bound_marker!{
+            wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], cb = [$($cb)+], cbargs = [$($cbargs)*],
+            path = [$($path)* :: $seg], rest = [$($rest)*]
+        }
+    };
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     path = [$($path:tt)*], rest = [$($rest:tt)*]) => {
+        $crate::__assert_bound_munch!{@cont
+            wheres = [$($wheres)* __EXPR_TYPE: ? $($path)*,],
+            impls = [$($impls)* ? $($path)*],
+            bounds = [$($bounds)* [? $($path)*]],
+            cb = [$($cb)+], cbargs = [$($cbargs)*], rest = [$($rest)*]}
+    };
+}
+
+/// Consumes `:: segment` path pieces one at a time, then dispatches to
+/// [`__assert_bound_args`] if the path is followed by `<`. A bare
+/// `$(:: $seg:ident)*` repetition followed by unbounded `tt` is ambiguous to
+/// the macro matcher, hence the one-segment-at-a-time recursion.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_bound_path {
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], path = [$($path:tt)*], rest = [:: $seg:ident $($rest:tt)*]) => {
+        $crate::__assert_bound_path!{
+            wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], cb = [$($cb)+], cbargs = [$($cbargs)*],
+            hrtb = [$($hrtb)*], path = [$($path)* :: $seg], rest = [$($rest)*]
+        }
+    };
+    // Bound has generic args: `Path<args>`.
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], path = [$($path:tt)*], rest = [< $($rest:tt)*]) => {
+        $crate::__assert_bound_args!{
+            wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], cb = [$($cb)+], cbargs = [$($cbargs)*],
+            hrtb = [$($hrtb)*], head = [$($path)*], out = [], rest = [$($rest)*]
+        }
+    };
+    // Fn-sugar bound, e.g. `Fn(u32) -> u32`: the parenthesized args arrive as
+    // a single `tt` (a parenthesized group is one token tree), so it can be
+    // captured directly; the return type can't (a `ty` fragment can't be
+    // followed by `+`), hence the hand-off to `__assert_bound_fn_ret`.
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], path = [$($path:tt)*], rest = [$args:tt -> $($rest:tt)*]) => {
+        $crate::__assert_bound_fn_ret!{
+            wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], cb = [$($cb)+], cbargs = [$($cbargs)*],
+            hrtb = [$($hrtb)*], path = [$($path)*], args = [$args], ret = [], rest = [$($rest)*]
+        }
+    };
+    // Bound has no generic args.
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], path = [$($path:tt)*], rest = [$($rest:tt)*]) => {
+        $crate::__assert_bound_munch!{@cont
+            wheres = [$($wheres)* __EXPR_TYPE: $($hrtb)* $($path)*,],
+            impls = [$($impls)* $($hrtb)* $($path)*],
+            bounds = [$($bounds)* [$($hrtb)* $($path)*]],
+            cb = [$($cb)+], cbargs = [$($cbargs)*], rest = [$($rest)*]}
+    };
+}
+
+/// Consumes the return type of an `Fn(..) -> Ret` bound one `tt` at a time:
+/// `Ret` can't be captured with a single `$ret:ty` fragment since `ty` can't
+/// be followed by `+` (the next bound's separator), for the same reason
+/// [`__assert_bound_path`] can't capture a plain path with a trailing `tt`.
+/// Only the explicit-return-type form is supported; `Fn(u32)` (implicit
+/// `-> ()`) is not.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_bound_fn_ret {
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], path = [$($path:tt)*], args = [$args:tt], ret = [$($ret:tt)*], rest = [+ $($rest:tt)*]) => {
+        $crate::__assert_bound_munch!{@cont
+            wheres = [$($wheres)* __EXPR_TYPE: $($hrtb)* $($path)*$args -> $($ret)*,],
+            impls = [$($impls)* $($hrtb)* $($path)*$args -> $($ret)*],
+            bounds = [$($bounds)* [$($hrtb)* $($path)*$args -> $($ret)*]],
+            cb = [$($cb)+], cbargs = [$($cbargs)*], rest = [+ $($rest)*]}
+    };
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], path = [$($path:tt)*], args = [$args:tt], ret = [$($ret:tt)*], rest = [; $($rest:tt)*]) => {
+        $crate::__assert_bound_munch!{@cont
+            wheres = [$($wheres)* __EXPR_TYPE: $($hrtb)* $($path)*$args -> $($ret)*,],
+            impls = [$($impls)* $($hrtb)* $($path)*$args -> $($ret)*],
+            bounds = [$($bounds)* [$($hrtb)* $($path)*$args -> $($ret)*]],
+            cb = [$($cb)+], cbargs = [$($cbargs)*], rest = [; $($rest)*]}
+    };
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], path = [$($path:tt)*], args = [$args:tt], ret = [$($ret:tt)*], rest = []) => {
+        $crate::__assert_bound_munch!{@cont
+            wheres = [$($wheres)* __EXPR_TYPE: $($hrtb)* $($path)*$args -> $($ret)*,],
+            impls = [$($impls)* $($hrtb)* $($path)*$args -> $($ret)*],
+            bounds = [$($bounds)* [$($hrtb)* $($path)*$args -> $($ret)*]],
+            cb = [$($cb)+], cbargs = [$($cbargs)*], rest = []}
+    };
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], path = [$($path:tt)*], args = [$args:tt], ret = [$($ret:tt)*], rest = [$next:tt $($rest:tt)*]) => {
+        $crate::__assert_bound_fn_ret!{
+            wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], cb = [$($cb)+], cbargs = [$($cbargs)*],
+            hrtb = [$($hrtb)*], path = [$($path)*], args = [$args], ret = [$($ret)* $next], rest = [$($rest)*]
+        }
+    };
+}
+
+/// Parses the comma-separated contents of `Path< ... >`, where each entry is
+/// either an ordinary type parameter (`Vec<u32>`) or an associated-type
+/// equality bound (`Item = u32`), possibly mixed, e.g. `Foo<A, C, B = u32>`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_bound_args {
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], head = [$($head:tt)*], out = [$($out:tt)*], rest = [> $($rest:tt)*]) => {
+        $crate::__assert_bound_munch!{@cont
+            wheres = [$($wheres)* __EXPR_TYPE: $($hrtb)* $($head)*<$($out)*>,],
+            impls = [$($impls)* $($hrtb)* $($head)*<$($out)*>],
+            bounds = [$($bounds)* [$($hrtb)* $($head)*<$($out)*>]],
+            cb = [$($cb)+], cbargs = [$($cbargs)*], rest = [$($rest)*]}
+    };
+    // Associated-type equality entry, e.g. `Item = u32`.
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], head = [$($head:tt)*], out = [$($out:tt)*], rest = [$assoc:ident = $aty:ty , $($rest:tt)*]) => {
+        $crate::__assert_bound_args!{
+            wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], cb = [$($cb)+], cbargs = [$($cbargs)*],
+            hrtb = [$($hrtb)*], head = [$($head)*], out = [$($out)* $assoc = $aty ,], rest = [$($rest)*]
+        }
+    };
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], head = [$($head:tt)*], out = [$($out:tt)*], rest = [$assoc:ident = $aty:ty > $($rest:tt)*]) => {
+        $crate::__assert_bound_args!{
+            wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], cb = [$($cb)+], cbargs = [$($cbargs)*],
+            hrtb = [$($hrtb)*], head = [$($head)*], out = [$($out)* $assoc = $aty], rest = [> $($rest)*]
+        }
+    };
+    // Ordinary type parameter entry, e.g. `Vec<u32>`.
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], head = [$($head:tt)*], out = [$($out:tt)*], rest = [$ty:ty , $($rest:tt)*]) => {
+        $crate::__assert_bound_args!{
+            wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], cb = [$($cb)+], cbargs = [$($cbargs)*],
+            hrtb = [$($hrtb)*], head = [$($head)*], out = [$($out)* $ty ,], rest = [$($rest)*]
+        }
+    };
+    (wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], cb = [$($cb:tt)+], cbargs = [$($cbargs:tt)*],
+     hrtb = [$($hrtb:tt)*], head = [$($head:tt)*], out = [$($out:tt)*], rest = [$ty:ty > $($rest:tt)*]) => {
+        $crate::__assert_bound_args!{
+            wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], cb = [$($cb)+], cbargs = [$($cbargs)*],
+            hrtb = [$($hrtb)*], head = [$($head)*], out = [$($out)* $ty], rest = [> $($rest)*]
+        }
+    };
+}
+
+/// [`__assert_bound_munch`] callback that builds the final `assert_bound!` expansion.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_bound_finish {
+    (expr = [$e:expr], wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], lifetimes = [$($lifetimes:tt)*]) => {
+        // Lambda is needed for discarding $e (lambda is returned from macro so
+        // it's possible to call it in order to not discard)
+        || {
+            /// Assert that `T` implements traits these were given to macro
+            #[inline(always)]
+            fn assert_bound<__EXPR_TYPE>(_: &__EXPR_TYPE)
+            where
+                $($wheres)*
+            {}
+
+            let expr = $e;
+            // Assert that $e implement traits
+            assert_bound(&expr);
+            // Return $e from lambda
+            expr
+        }
+    };
+}
+
 /// Assert that expression implements trait(s) at compile-time.
 ///
 /// ## Examples
@@ -23,6 +278,79 @@
 ///
 /// assert_bound!(() => T);
 /// ```
+/// Associated-type equality bounds are supported too, which is handy for
+/// asserting things about iterators and futures:
+/// ```
+/// # use assert_bound::assert_bound;
+/// assert_bound!(vec![1u32].into_iter() => Iterator<Item = u32> + ExactSizeIterator);
+/// ```
+/// Multiple equality entries, and ordinary type parameters mixed in before
+/// them (matching Rust's own rule that generic args precede constraints),
+/// are supported within the same `<...>`:
+/// ```
+/// # use assert_bound::assert_bound;
+/// trait TwoAssoc {
+///     type X;
+///     type Y;
+/// }
+/// impl TwoAssoc for () {
+///     type X = u32;
+///     type Y = String;
+/// }
+/// assert_bound!(() => TwoAssoc<X = u32, Y = String>);
+///
+/// trait Three<A, C> {
+///     type B;
+/// }
+/// impl Three<u32, String> for () {
+///     type B = bool;
+/// }
+/// assert_bound!(() => Three<u32, String, B = bool>);
+/// ```
+/// An equality bound followed by an unrelated bound in the same `+`-list
+/// parses and type-checks just as you'd expect:
+/// ```
+/// # use assert_bound::assert_bound;
+/// assert_bound!(vec![1u32].into_iter() => Iterator<Item = u32> + ?Sized);
+/// ```
+/// As are lifetime and relaxed (`?Sized`/marker) bounds, which is exactly
+/// what you want to check before handing an iterator/future off to a spawner:
+/// ```
+/// # use assert_bound::assert_bound;
+/// async fn f() {}
+/// assert_bound!(f() => Send + 'static);
+/// assert_bound!(String::new() => ?Sized);
+/// ```
+/// `Fn`/`FnMut`/`FnOnce` sugar is supported too, as long as the return type
+/// is given explicitly (`Fn(u32)`, implicitly returning `()`, is not):
+/// ```
+/// # use assert_bound::assert_bound;
+/// assert_bound!(|x: u32| x + 1 => Fn(u32) -> u32);
+/// ```
+/// A leading `for<'a>` prefix asserts a higher-ranked bound, which is exactly
+/// what closures that return a borrow usually only satisfy. Note that a bare
+/// closure literal is inferred against a single concrete lifetime rather than
+/// a higher-ranked one, so a named `fn` (or a value coerced to a `fn` pointer)
+/// is needed to actually witness the bound:
+/// ```
+/// # use assert_bound::assert_bound;
+/// fn echo(s: &str) -> &str { s }
+/// assert_bound!(echo => for<'a> Fn(&'a str) -> &'a str);
+/// ```
+/// A `for<'a>`-prefixed bound and an equality-bound trait parse fine
+/// together in the same `+`-list:
+/// ```
+/// # use assert_bound::assert_bound;
+/// trait Wrap {
+///     type Out;
+/// }
+/// impl Wrap for fn(&str) -> &str {
+///     type Out = u32;
+/// }
+///
+/// fn echo(s: &str) -> &str { s }
+/// assert_bound!(echo as fn(&str) -> &str => for<'a> Fn(&'a str) -> &'a str + Wrap<Out = u32>);
+/// ```
 /// **Note**: expression is **not** executed:
 /// ```
 /// # use assert_bound::assert_bound;
@@ -65,29 +393,207 @@
 /// ```
 #[macro_export]
 macro_rules! assert_bound {
-    (
-        $e:expr =>
-        // One trait bound, e.g. `std::fmt::Debug`, `PartialEq<()>`
-        $head:ident $( :: $tail:ident )* $( < $param:ty $(, $p:ty)* > )?
-        // Zero or more trait bounds splited by `+`
-        $(+ $head2:ident $( :: $tail2:ident )* $( < $param2:ty $(, $p2:ty)* > )?)*
-    ) => {
-        // Lambda is needed for discarding $e (lambda is returned from macro so
-        // it's possible to call it in order to not discard)
+    ($e:expr => $($rest:tt)+) => {
+        $crate::__assert_bound_munch!(@bound
+            wheres = [], impls = [], bounds = [],
+            cb = [$crate::__assert_bound_finish], cbargs = [expr = [$e],],
+            rest = [$($rest)+]
+        )
+    };
+}
+
+/// [`__assert_bound_munch`] callback that builds the final `assert_not_bound!` expansion.
+///
+/// Note this deliberately ignores `impls` (the bounds joined with `+`) in
+/// favor of `bounds` (the same bounds kept separate): `assert_not_bound!`
+/// must reject the type if it satisfies *any* of the given bounds, not only
+/// if it satisfies all of them jointly, so each bound needs its own
+/// specialization probe. See [`__assert_not_bound_probes`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_not_bound_finish {
+    (expr = [$e:expr], wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], lifetimes = [$($lifetimes:tt)*]) => {
         || {
-            /// Assert that `T` implements traits these were given to macro
+            let expr = $e;
+            $crate::__assert_not_bound_probes!(expr = [&expr], bounds = [$($bounds)*]);
+            expr
+        }
+    };
+}
+
+/// Runs one inference-ambiguity probe (the same trick
+/// `static_assertions::assert_not_impl_any!` uses, since negative trait
+/// bounds (`T: !Bound`) don't exist on stable) per bound in `bounds`, each in
+/// its own block so the `Invalid`/`AmbiguousIfImpl` names can be reused
+/// without clashing. `Invalid` only gets an `AmbiguousIfImpl<Invalid>` impl
+/// (in addition to the unconditional `AmbiguousIfImpl<()>` blanket impl
+/// below) when `__EXPR_TYPE` satisfies that one bound; at that point `_` can
+/// no longer be inferred and rustc refuses to compile with an
+/// ambiguous-type error. Probing bounds one at a time like this (rather than
+/// jointly, as a single `$($impls)*` would) is what gives `assert_not_bound!`
+/// its "none of these" semantics instead of "not all of these".
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_not_bound_probes {
+    (expr = [$e:expr], bounds = []) => {};
+    (expr = [$e:expr], bounds = [[$($bound:tt)*] $($rest:tt)*]) => {
+        {
+            trait AmbiguousIfImpl<__MARKER> {
+                fn assert_not_bound(&self) {}
+            }
+
+            impl<__EXPR_TYPE: ?Sized> AmbiguousIfImpl<()> for __EXPR_TYPE {}
+
+            struct Invalid;
+            impl<__EXPR_TYPE: ?Sized + $($bound)*> AmbiguousIfImpl<Invalid> for __EXPR_TYPE {}
+
+            AmbiguousIfImpl::<_>::assert_not_bound($e);
+        }
+        $crate::__assert_not_bound_probes!(expr = [$e], bounds = [$($rest)*]);
+    };
+}
+
+/// Assert that expression does **not** implement trait(s) at compile-time.
+///
+/// This is the inverse of [`assert_bound`]: it fails to compile if the
+/// expression's type *does* implement the given bound(s), instead of if it
+/// doesn't.
+///
+/// ## Examples
+/// ```
+/// # use assert_bound::assert_not_bound;
+/// struct NotClone;
+///
+/// assert_not_bound!(NotClone => Clone);
+/// ```
+/// ```compile_fail
+/// # use assert_bound::assert_not_bound;
+/// // `()` does implement `Clone`,
+/// // so rustc will fail to compile that.
+/// assert_not_bound!(() => Clone);
+/// ```
+/// As with [`assert_bound`], the expression is **not** executed unless you
+/// call the closure that's returned:
+/// ```
+/// # use assert_bound::assert_not_bound;
+/// struct NotClone;
+///
+/// let mut var = 0;
+/// assert_not_bound!({ var = 1; NotClone } => Clone);
+/// assert_eq!(var, 0);
+/// assert_not_bound!({ var = 1; NotClone } => Clone)();
+/// assert_eq!(var, 1);
+/// ```
+/// With more than one `+`-joined bound, the expression's type must implement
+/// **none** of them — each one is checked on its own, not jointly:
+/// ```
+/// # use assert_bound::assert_not_bound;
+/// use std::marker::PhantomData;
+///
+/// // Neither `Send` nor `Sync`, since it holds a raw pointer.
+/// struct NotSendSync(PhantomData<*const ()>);
+///
+/// assert_not_bound!(NotSendSync(PhantomData) => Send + Sync);
+/// ```
+/// ```compile_fail
+/// # use assert_bound::assert_not_bound;
+/// use std::cell::Cell;
+///
+/// // `Cell<i32>` *is* `Send` (just not `Sync`), so checking the pair must
+/// // still fail to compile, even though it isn't `Send + Sync` jointly.
+/// assert_not_bound!(Cell::<i32>::new(1) => Send + Sync);
+/// ```
+#[macro_export]
+macro_rules! assert_not_bound {
+    ($e:expr => $($rest:tt)+) => {
+        $crate::__assert_bound_munch!(@bound
+            wheres = [], impls = [], bounds = [],
+            cb = [$crate::__assert_not_bound_finish], cbargs = [expr = [$e],],
+            rest = [$($rest)+]
+        )
+    };
+}
+
+/// [`__assert_bound_munch`] callback that builds the final `as_opaque!` expansion.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __as_opaque_finish {
+    (expr = [$e:expr], wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], lifetimes = []) => {
+        $crate::__as_opaque_finish!(
+            expr = [$e], wheres = [$($wheres)*], impls = [$($impls)*], bounds = [$($bounds)*], lifetimes = ['static]
+        )
+    };
+    (expr = [$e:expr], wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], lifetimes = ['static]) => {
+        {
+            /// Cast type to anonymous type that implements trait(s) these were given to macro
             #[inline(always)]
-            fn assert_bound<__EXPR_TYPE>(_: &__EXPR_TYPE)
+            fn as_opaque<'lifetime, __EXPR_TYPE>(expr: __EXPR_TYPE)
+                -> impl $($impls)* + 'lifetime
             where
-                __EXPR_TYPE: $head $( :: $tail )* $( < $param $(, $p)* > )?,
-                $( __EXPR_TYPE: $head2 $( :: $tail2 )* $( < $param2 $(, $p2)* > )? , )*
-            {}
+                $($wheres)*
+                __EXPR_TYPE: 'lifetime,
+            {
+                expr
+            }
 
             let expr = $e;
-            // Assert that $e implement traits
-            assert_bound(&$e);
-            // Return $e from lambda
-            expr
+            let opaque = as_opaque::<'static, _>(expr);
+            opaque
+        }
+    };
+    // Any other lifetime(s), e.g. `; 'a` or `; 'a + 'b`: hand off to a muncher
+    // that turns the `+`-separated list into a matching list of generic
+    // lifetime parameters.
+    (expr = [$e:expr], wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], lifetimes = [$($lifetimes:tt)+]) => {
+        $crate::__as_opaque_lifetimes!(
+            expr = [$e], wheres = [$($wheres)*], impls = [$($impls)*],
+            gen = [], ltwheres = [], ltimpls = [],
+            rest = [$($lifetimes)+]
+        )
+    };
+}
+
+/// Parses the `+`-separated lifetime list following [`as_opaque`]'s `;`
+/// suffix (e.g. `'a + 'b`) one lifetime at a time, building up a matching
+/// list of generic lifetime parameters (`gen`), `__EXPR_TYPE: 'lifetime,`
+/// where-predicates (`ltwheres`) and an `+ 'lifetime` impl-bound suffix
+/// (`ltimpls`), then builds the final `as_opaque!` expansion.
+///
+/// Unlike the `'static` case in [`__as_opaque_finish`], the captured
+/// lifetimes are used as the generic parameters' names directly (instead of
+/// through a fixed placeholder name), so bounds that name them (e.g.
+/// `Iterator<Item = &'a u32>`) refer to the helper fn's own parameters.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __as_opaque_lifetimes {
+    (expr = [$e:expr], wheres = [$($wheres:tt)*], impls = [$($impls:tt)*],
+     gen = [$($gen:tt)*], ltwheres = [$($ltwheres:tt)*], ltimpls = [$($ltimpls:tt)*],
+     rest = [$lt:lifetime + $($rest:tt)+]) => {
+        $crate::__as_opaque_lifetimes!(
+            expr = [$e], wheres = [$($wheres)*], impls = [$($impls)*],
+            gen = [$($gen)* $lt,], ltwheres = [$($ltwheres)* __EXPR_TYPE: $lt,], ltimpls = [$($ltimpls)* + $lt],
+            rest = [$($rest)+]
+        )
+    };
+    (expr = [$e:expr], wheres = [$($wheres:tt)*], impls = [$($impls:tt)*],
+     gen = [$($gen:tt)*], ltwheres = [$($ltwheres:tt)*], ltimpls = [$($ltimpls:tt)*],
+     rest = [$lt:lifetime]) => {
+        {
+            /// Cast type to anonymous type that implements trait(s) these were given to macro
+            #[inline(always)]
+            fn as_opaque<$($gen)* $lt, __EXPR_TYPE>(expr: __EXPR_TYPE)
+                -> impl $($impls)* $($ltimpls)* + $lt
+            where
+                $($wheres)*
+                $($ltwheres)*
+                __EXPR_TYPE: $lt,
+            {
+                expr
+            }
+
+            let expr = $e;
+            let opaque = as_opaque::<$($gen)* $lt, _>(expr);
+            opaque
         }
     };
 }
@@ -108,6 +614,64 @@ macro_rules! assert_bound {
 /// assert_eq!(t.partial_cmp(&()), Some(std::cmp::Ordering::Equal));
 /// ```
 ///
+/// Associated-type equality bounds work here too:
+/// ```
+/// # use assert_bound::as_opaque;
+/// let it = as_opaque!(vec![1u32].into_iter() => Iterator<Item = u32>);
+/// assert_eq!(it.collect::<Vec<_>>(), vec![1u32]);
+/// ```
+/// ...including multiple equality entries, and ordinary type parameters
+/// mixed in before them (matching Rust's own rule that generic args precede
+/// constraints), within the same `<...>`:
+/// ```
+/// # use assert_bound::as_opaque;
+/// trait TwoAssoc {
+///     type X;
+///     type Y;
+/// }
+/// impl TwoAssoc for () {
+///     type X = u32;
+///     type Y = String;
+/// }
+/// as_opaque!(() => TwoAssoc<X = u32, Y = String>);
+///
+/// trait Three<A, C> {
+///     type B;
+/// }
+/// impl Three<u32, String> for () {
+///     type B = bool;
+/// }
+/// as_opaque!(() => Three<u32, String, B = bool>);
+/// ```
+///
+/// By default the returned opaque type is bound by `'static`, but a trailing
+/// `; 'lifetime` (or `; 'lifetime1 + 'lifetime2 + ...` for more than one) lets
+/// you capture borrows instead:
+/// ```
+/// # use assert_bound::as_opaque;
+/// fn borrow<'a: 'b, 'b>(v: &'a Vec<u32>, _b: &'b ()) -> impl Iterator<Item = &'a u32> + 'a + 'b {
+///     as_opaque!(v.iter() => Iterator<Item = &'a u32> ; 'a + 'b)
+/// }
+///
+/// let v = vec![1u32, 2, 3];
+/// assert_eq!(borrow(&v, &()).collect::<Vec<_>>(), vec![&1, &2, &3]);
+/// ```
+///
+/// A leading `for<'a>` prefix works here too, and combines with the `;
+/// 'lifetime` suffix like you'd expect. As with [`assert_bound`], a named
+/// `fn` is used rather than a closure literal, since the latter is inferred
+/// against a single concrete lifetime rather than a higher-ranked one:
+/// ```
+/// # use assert_bound::as_opaque;
+/// fn echo(s: &str) -> &str { s }
+///
+/// fn make_echo<'a>(_v: &'a ()) -> impl for<'b> Fn(&'b str) -> &'b str + 'a {
+///     as_opaque!(echo => for<'b> Fn(&'b str) -> &'b str ; 'a)
+/// }
+///
+/// assert_eq!(make_echo(&())("hi"), "hi");
+/// ```
+///
 /// ```compile_fail
 /// # use assert_bound::{as_opaque, assert_bound};
 /// assert_bound!(&(), Eq); // OK
@@ -137,49 +701,287 @@ macro_rules! assert_bound {
 /// ```
 #[macro_export]
 macro_rules! as_opaque {
-    (
-        $e:expr =>
-        // One trait bound, e.g. `std::fmt::Debug`, `PartialEq<()>`
-        $head:ident $( :: $tail:ident )* $( < $param:ty $(, $p:ty)* > )?
-        // Zero or more trait bounds splited by `+`
-        $(+ $head2:ident $( :: $tail2:ident )* $( < $param2:ty $(, $p2:ty)* > )?)*
-        // lifetime
-        ; $lifetime:tt
-    ) => {
-            {
-                /// Cast type to anonymous type that implements trait(s) these were given to macro
-                #[inline(always)]
-                fn as_opaque<'lifetime, __EXPR_TYPE>(expr: __EXPR_TYPE)
-                    -> impl $head $( :: $tail )* $( < $param $(, $p)* > )?
-                    $(+ $head2 $( :: $tail2 )* $( < $param2 $(, $p2)* > )? )*
-                    + 'lifetime
-                where
-                    __EXPR_TYPE: $head $( :: $tail )* $( < $param $(, $p)* > )?,
-                    $( __EXPR_TYPE: $head2 $( :: $tail2 )* $( < $param2 $(, $p2)* > )? , )*
-                    __EXPR_TYPE: 'lifetime,
-                {
-                    expr
-                }
+    ($e:expr => $($rest:tt)+) => {
+        $crate::__assert_bound_munch!(@bound
+            wheres = [], impls = [], bounds = [],
+            cb = [$crate::__as_opaque_finish], cbargs = [expr = [$e],],
+            rest = [$($rest)+]
+        )
+    };
+}
 
-                let expr = $e;
-                let opaque = as_opaque::<$lifetime, _>(expr);
-                opaque
+/// [`__assert_bound_munch`] callback that builds the final `named_opaque!` expansion.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __named_opaque_finish {
+    (name = [$Name:ident], wheres = [$($wheres:tt)*], impls = [$($impls:tt)*], bounds = [$($bounds:tt)*], lifetimes = [$($lifetimes:tt)*]) => {
+        pub struct $Name<T>(T);
+
+        impl<T: $($impls)*> $Name<T> {
+            #[inline]
+            pub fn new(value: T) -> Self {
+                $Name(value)
+            }
+        }
+
+        impl<T> $Name<T> {
+            #[inline]
+            pub fn into_inner(self) -> T {
+                self.0
             }
+        }
+
+        $crate::__named_opaque_if_iterator!(
+            [$($impls)*]
+            then [
+                impl<T: $($impls)*> Iterator for $Name<T> {
+                    type Item = <T as Iterator>::Item;
+
+                    #[inline]
+                    fn next(&mut self) -> Option<Self::Item> {
+                        self.0.next()
+                    }
+
+                    #[inline]
+                    fn size_hint(&self) -> (usize, Option<usize>) {
+                        self.0.size_hint()
+                    }
+                }
+            ]
+            else []
+        );
+
+        $crate::__named_opaque_if_debug!(
+            [$($impls)*]
+            then [
+                impl<T: $($impls)*> ::core::fmt::Debug for $Name<T> {
+                    #[inline]
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        ::core::fmt::Debug::fmt(&self.0, f)
+                    }
+                }
+            ]
+            else []
+        );
+
+        $crate::__named_opaque_if_display!(
+            [$($impls)*]
+            then [
+                impl<T: $($impls)*> ::core::fmt::Display for $Name<T> {
+                    #[inline]
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        ::core::fmt::Display::fmt(&self.0, f)
+                    }
+                }
+            ]
+            else []
+        );
+
+        $crate::__named_opaque_if_future!(
+            [$($impls)*]
+            then [
+                impl<T: $($impls)*> ::core::future::Future for $Name<T> {
+                    type Output = <T as ::core::future::Future>::Output;
+
+                    #[inline]
+                    fn poll(
+                        self: ::core::pin::Pin<&mut Self>,
+                        cx: &mut ::core::task::Context<'_>,
+                    ) -> ::core::task::Poll<Self::Output> {
+                        // SAFETY: `$Name` is a transparent, `Drop`-less newtype
+                        // around its only field, so projecting through the pin
+                        // is sound.
+                        unsafe { self.map_unchecked_mut(|named| &mut named.0) }.poll(cx)
+                    }
+                }
+            ]
+            else []
+        );
+    };
+}
+
+/// Expands to `$then` if `Iterator` appears anywhere in the given bound list,
+/// `$else` otherwise. Used by [`__named_opaque_finish`] to pick which of the
+/// curated traits to forward, one muncher per trait since `macro_rules!`
+/// has no way to compare two captured idents for equality other than
+/// matching a hardcoded one literally.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __named_opaque_if_iterator {
+    ([Iterator $($rest:tt)*] then [$($then:tt)*] else [$($else:tt)*]) => {
+        $($then)*
+    };
+    ([$other:tt $($rest:tt)*] then [$($then:tt)*] else [$($else:tt)*]) => {
+        $crate::__named_opaque_if_iterator!([$($rest)*] then [$($then)*] else [$($else)*]);
+    };
+    ([] then [$($then:tt)*] else [$($else:tt)*]) => {
+        $($else)*
     };
+}
 
-    // Variant without lifetime (use default 'static)
-    (
-        $e:expr =>
-        // One trait bound, e.g. `std::fmt::Debug`, `PartialEq<()>`
-        $head:ident $( :: $tail:ident )* $( < $param:ty $(, $p:ty)* > )?
-        // Zero or more trait bounds splited by `+`
-        $(+ $head2:ident $( :: $tail2:ident )* $( < $param2:ty $(, $p2:ty)* > )?)*
-    ) => {
-        $crate::as_opaque!(
-            $e =>
-            $head $( :: $tail )* $( < $param $(, $p)* > )?
-            $(+ $head2 $( :: $tail2 )* $( < $param2 $(, $p2)* > )?)*
-            ; 'static
-        )
+/// Same as [`__named_opaque_if_iterator`], but for `Debug`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __named_opaque_if_debug {
+    ([Debug $($rest:tt)*] then [$($then:tt)*] else [$($else:tt)*]) => {
+        $($then)*
+    };
+    ([$other:tt $($rest:tt)*] then [$($then:tt)*] else [$($else:tt)*]) => {
+        $crate::__named_opaque_if_debug!([$($rest)*] then [$($then)*] else [$($else)*]);
+    };
+    ([] then [$($then:tt)*] else [$($else:tt)*]) => {
+        $($else)*
+    };
+}
+
+/// Same as [`__named_opaque_if_iterator`], but for `Display`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __named_opaque_if_display {
+    ([Display $($rest:tt)*] then [$($then:tt)*] else [$($else:tt)*]) => {
+        $($then)*
+    };
+    ([$other:tt $($rest:tt)*] then [$($then:tt)*] else [$($else:tt)*]) => {
+        $crate::__named_opaque_if_display!([$($rest)*] then [$($then)*] else [$($else)*]);
+    };
+    ([] then [$($then:tt)*] else [$($else:tt)*]) => {
+        $($else)*
+    };
+}
+
+/// Same as [`__named_opaque_if_iterator`], but for `Future`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __named_opaque_if_future {
+    ([Future $($rest:tt)*] then [$($then:tt)*] else [$($else:tt)*]) => {
+        $($then)*
+    };
+    ([$other:tt $($rest:tt)*] then [$($then:tt)*] else [$($else:tt)*]) => {
+        $crate::__named_opaque_if_future!([$($rest)*] then [$($then)*] else [$($else)*]);
+    };
+    ([] then [$($then:tt)*] else [$($else:tt)*]) => {
+        $($else)*
+    };
+}
+
+/// Mint a concrete, nameable newtype around an otherwise unnameable
+/// expression type (the return type of an `async fn`, a closure, ...),
+/// forwarding whichever of a curated set of standard traits — [`Iterator`],
+/// [`Debug`], [`Display`] and [`Future`] — appear in the given bound list.
+///
+/// Unlike [`as_opaque`], which returns an anonymous `impl Trait`,
+/// `named_opaque!` gives you an actual struct you can name in your own
+/// public API, e.g. as the return type of a method.
+///
+/// ```
+/// # use assert_bound::named_opaque;
+/// # use std::fmt::Debug;
+/// named_opaque! {
+///     struct MyIter: Iterator<Item = u32> + Debug;
+/// }
+///
+/// let mut it = MyIter::new(vec![1u32, 2, 3].into_iter());
+/// assert_eq!(it.next(), Some(1));
+/// assert_eq!(format!("{:?}", it), format!("{:?}", vec![2u32, 3].into_iter()));
+/// ```
+///
+/// `Display` is forwarded the same way:
+/// ```
+/// # use assert_bound::named_opaque;
+/// # use std::fmt::Display;
+/// named_opaque! {
+///     struct Loud: Display;
+/// }
+///
+/// let loud = Loud::new("hello");
+/// assert_eq!(format!("{}", loud), "hello");
+/// ```
+///
+/// So is `Future`, which is the main reason this macro exists: it lets you
+/// name the return type of an `async fn` (or a combinator built out of one)
+/// without boxing it. The generated struct is itself `Future`, so it can be
+/// `.await`ed just like the type it wraps:
+/// ```
+/// # use assert_bound::named_opaque;
+/// # use std::future::Future;
+/// # use std::pin::pin;
+/// # use std::sync::Arc;
+/// # use std::task::{Context, Poll, Wake};
+/// named_opaque! {
+///     struct FetchResult: Future<Output = u32>;
+/// }
+///
+/// async fn fetch() -> u32 {
+///     42
+/// }
+///
+/// fn fetch_named() -> FetchResult<impl Future<Output = u32>> {
+///     FetchResult::new(fetch())
+/// }
+///
+/// // A no-op waker is enough here since `fetch_named()` never actually
+/// // suspends; this is just to drive the future to completion without
+/// // pulling in an async runtime as a doctest dependency.
+/// struct NoopWaker;
+/// impl Wake for NoopWaker {
+///     fn wake(self: Arc<Self>) {}
+/// }
+///
+/// let waker = Arc::new(NoopWaker).into();
+/// let mut cx = Context::from_waker(&waker);
+/// let mut fut = pin!(fetch_named());
+/// match fut.as_mut().poll(&mut cx) {
+///     Poll::Ready(value) => assert_eq!(value, 42),
+///     Poll::Pending => unreachable!("fetch_named() never actually suspends"),
+/// }
+/// ```
+///
+/// `Fn`/`FnMut`/`FnOnce` bounds are accepted by the grammar (so constructing
+/// a wrapper around a closure works fine), but aren't forwarded: implementing
+/// those traits for a custom type needs the unstable `fn_traits`/
+/// `unboxed_closures` features, which this crate avoids since everything
+/// else here works on stable. Use the generated `into_inner` method to get
+/// the closure back out instead.
+/// ```
+/// # use assert_bound::named_opaque;
+/// named_opaque! {
+///     struct Adder: Fn(u32) -> u32;
+/// }
+///
+/// let adder = Adder::new(|x: u32| x + 1);
+/// assert_eq!((adder.into_inner())(41), 42);
+/// ```
+///
+/// The generated struct is a real, nameable item, so (unlike the examples
+/// above, which are wrapped in an implicit `fn main`) it can be declared at
+/// module scope and used as the return type of a separate function — the
+/// scenario this macro exists for in the first place:
+/// ```
+/// # use assert_bound::named_opaque;
+/// # use std::fmt::Display;
+/// named_opaque! {
+///     struct Greeting: Display;
+/// }
+///
+/// fn greeting() -> Greeting<&'static str> {
+///     Greeting::new("hi")
+/// }
+///
+/// fn main() {
+///     assert_eq!(format!("{}", greeting()), "hi");
+/// }
+/// ```
+#[macro_export]
+macro_rules! named_opaque {
+    // The trailing `;` is part of `$bounds` itself (not matched separately,
+    // to avoid a repetition/literal ambiguity): it's consumed by
+    // `__assert_bound_munch`'s `@cont` state, which already treats a bare
+    // trailing `;` as "no lifetimes".
+    (struct $Name:ident : $($bounds:tt)+) => {
+        $crate::__assert_bound_munch!{@bound
+            wheres = [], impls = [], bounds = [],
+            cb = [$crate::__named_opaque_finish], cbargs = [name = [$Name],],
+            rest = [$($bounds)+]
+        }
     };
 }